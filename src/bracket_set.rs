@@ -0,0 +1,103 @@
+use std::collections::{HashMap, HashSet};
+
+///
+/// A configurable set of opening/closing bracket pairs to match against.
+///
+/// [`create_longest_substring`](crate::create_longest_substring) is hardwired to the three
+/// ASCII pairs `()`, `[]`, `{}`; a `BracketSet` lets callers match other delimiters (angle
+/// brackets, guillemets, or anything else) via
+/// [`create_longest_substring_with_set`](crate::create_longest_substring_with_set).
+///
+#[derive(Debug, Clone)]
+pub struct BracketSet {
+    opening_to_closing: HashMap<char, char>,
+    closing_to_opening: HashMap<char, char>,
+    closing: HashSet<char>,
+}
+
+impl BracketSet {
+    ///
+    /// Builds a bracket set from the given `(opening, closing)` pairs.
+    ///
+    pub fn new(pairs: impl IntoIterator<Item = (char, char)>) -> Self {
+        let mut opening_to_closing = HashMap::new();
+        let mut closing_to_opening = HashMap::new();
+        let mut closing = HashSet::new();
+
+        for (open, close) in pairs {
+            opening_to_closing.insert(open, close);
+            closing_to_opening.insert(close, open);
+            closing.insert(close);
+        }
+
+        Self {
+            opening_to_closing,
+            closing_to_opening,
+            closing,
+        }
+    }
+
+    ///
+    /// Checks if provided char is one of this set's opening or closing brackets.
+    ///
+    pub(crate) fn is_bracket(&self, val: char) -> bool {
+        self.opening_to_closing.contains_key(&val) || self.closing.contains(&val)
+    }
+
+    ///
+    /// Checks if provided char is one of this set's closing brackets.
+    ///
+    pub(crate) fn is_closing(&self, val: char) -> bool {
+        self.closing.contains(&val)
+    }
+
+    ///
+    /// Attempts to map given bracket to its closing pair. Returns `None` if given char
+    /// isn't one of this set's opening brackets.
+    ///
+    pub(crate) fn opening_bracket_to_closing(&self, val: char) -> Option<char> {
+        self.opening_to_closing.get(&val).copied()
+    }
+
+    ///
+    /// Attempts to map given bracket to its opening pair. Returns `None` if given char
+    /// isn't one of this set's closing brackets.
+    ///
+    pub(crate) fn closing_bracket_to_opening(&self, val: char) -> Option<char> {
+        self.closing_to_opening.get(&val).copied()
+    }
+}
+
+impl Default for BracketSet {
+    ///
+    /// The three ASCII pairs `()`, `[]`, `{}`.
+    ///
+    fn default() -> Self {
+        Self::new([('{', '}'), ('[', ']'), ('(', ')')])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_set_matches_ascii_pairs() {
+        let set = BracketSet::default();
+        assert_eq!(set.opening_bracket_to_closing('('), Some(')'));
+        assert_eq!(set.opening_bracket_to_closing('['), Some(']'));
+        assert_eq!(set.opening_bracket_to_closing('{'), Some('}'));
+        assert_eq!(set.opening_bracket_to_closing(')'), None);
+        assert!(set.is_bracket(')'));
+        assert!(!set.is_bracket('a'));
+    }
+
+    #[test]
+    fn custom_set() {
+        let set = BracketSet::new([('<', '>'), ('«', '»')]);
+        assert_eq!(set.opening_bracket_to_closing('<'), Some('>'));
+        assert_eq!(set.opening_bracket_to_closing('«'), Some('»'));
+        assert!(set.is_bracket('»'));
+        assert!(!set.is_bracket('('));
+    }
+}
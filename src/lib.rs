@@ -3,50 +3,115 @@
 //! characters.
 //!
 
+mod bracket_set;
+mod maximal_substrings;
+mod repair;
+
+use std::borrow::Cow;
+
+pub use bracket_set::BracketSet;
+pub use maximal_substrings::{maximal_valid_substrings, maximal_valid_substrings_with_set};
+pub use repair::{min_repair_insertions, repair};
+
+/// The longest valid-bracket span found by [`find_longest_span`], in byte offsets into the
+/// original (non-cyclic) string.
+enum Span {
+    /// The whole (infinitely repeated) string is valid.
+    Infinite,
+    /// A contiguous, non-wrapping slice `val[start..end]`.
+    Contiguous { start: usize, end: usize },
+    /// A cyclic match that wraps around the end of the string back to its start:
+    /// `val[start..]` followed by `val[..end]`.
+    WrapAround { start: usize, end: usize },
+}
+
 ///
-/// Checks if provided char is a bracket.
+/// Produces longest substring with valid brackets of infinite string `val` using its
+/// characters, matching against the default [`BracketSet`] (`()`, `[]`, `{}`). If string
+/// is infinite, returns "Infinite".
 ///
-fn is_bracket(val: char) -> bool {
-    match val {
-        '{' | '[' | '(' | ')' | ']' | '}' => true,
-        _ => false,
-    }
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn create_longest_substring(val: &str) -> String {
+    create_longest_substring_with_set(val, &BracketSet::default())
 }
 
 ///
-/// Attempts to map given bracket to its closing pair. Returns `None` if given char
-/// isn't opening bracket.
+/// Produces longest substring with valid brackets of infinite string `val` using its
+/// characters, matching against the given `set` of bracket pairs. If string is infinite,
+/// returns "Infinite".
 ///
-fn opening_bracket_to_closing(val: char) -> Option<char> {
-    match val {
-        '{' => Some('}'),
-        '[' => Some(']'),
-        '(' => Some(')'),
-        _ => None,
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn create_longest_substring_with_set(val: &str, set: &BracketSet) -> String {
+    match find_longest_span(val, set) {
+        Span::Infinite => "Infinite".to_owned(),
+        Span::Contiguous { start, end } => val[start..end].to_owned(),
+        Span::WrapAround { start, end } => format!("{}{}", &val[start..], &val[..end]),
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Error {
-    /// Encoded char size is greater than 1 byte
-    NonByteChar,
+///
+/// Produces longest substring with valid brackets of infinite string `val` using its
+/// characters, matching against the default [`BracketSet`] (`()`, `[]`, `{}`).
+///
+/// Returns a borrowed slice of `val` (or the borrowed literal `"Infinite"`) whenever the
+/// answer doesn't need to wrap around the end of the string, avoiding an allocation on
+/// that common path.
+///
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn create_longest_substring_cow(val: &str) -> Cow<'_, str> {
+    create_longest_substring_cow_with_set(val, &BracketSet::default())
 }
 
 ///
 /// Produces longest substring with valid brackets of infinite string `val` using its
-/// characters. If string is infinite, returns "Infinite".
+/// characters, matching against the given `set` of bracket pairs.
+///
+/// Returns a borrowed slice of `val` (or the borrowed literal `"Infinite"`) whenever the
+/// answer doesn't need to wrap around the end of the string, avoiding an allocation on
+/// that common path.
+///
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn create_longest_substring_cow_with_set<'a>(val: &'a str, set: &BracketSet) -> Cow<'a, str> {
+    match find_longest_span(val, set) {
+        Span::Infinite => Cow::Borrowed("Infinite"),
+        Span::Contiguous { start, end } => Cow::Borrowed(&val[start..end]),
+        Span::WrapAround { start, end } => Cow::Owned(format!("{}{}", &val[start..], &val[..end])),
+    }
+}
+
+///
+/// Finds the longest valid-bracket span of the infinite string `val`, matching against
+/// `set`. Returns [`Span::Infinite`] if the whole (infinitely repeated) string is valid.
 ///
-/// Returns `Err` in case if string contains char encoded with size greater than one byte.
+/// Works on arbitrary UTF-8 input: character positions are tracked separately from byte
+/// offsets, so the returned span always lands on char boundaries.
 ///
 /// Time complexity: O(n)
 /// Space complexity: O(n)
 ///
-pub fn create_longest_substring(val: &str) -> Result<String, Error> {
+fn find_longest_span(val: &str, set: &BracketSet) -> Span {
     #[derive(Debug, Copy, Clone)]
     struct CharPos {
         val: char,
         index: usize,
     }
+
+    // Byte offset of the char at each char index, plus the total byte length as a
+    // sentinel one-past-the-end entry so a char index can always be resolved to a byte
+    // offset, including the end of the string.
+    let mut char_byte_offsets: Vec<usize> = val.char_indices().map(|(offset, _)| offset).collect();
+    char_byte_offsets.push(val.len());
+    let num_chars = char_byte_offsets.len() - 1;
+    let byte_offset = |char_index: usize| char_byte_offsets[char_index];
+
     let mut brackets = Vec::<CharPos>::new();
 
     let mut max_end = 0;
@@ -55,18 +120,15 @@ pub fn create_longest_substring(val: &str) -> Result<String, Error> {
     // Length of valid sequential substring predecessor
     let mut prev_valid_len = 0;
 
-    for (index, char) in val.chars().cycle().take(2 * val.len()).enumerate() {
-        if char.len_utf8() > 1 || char.len_utf16() > 1 {
-            // Encoded char size is greater than one byte
-            return Err(Error::NonByteChar);
-        } else if let Some(len) = if is_bracket(char) {
-            if let Some(bracket) = opening_bracket_to_closing(char) {
-                if index >= val.len()
+    for (index, char) in val.chars().cycle().take(2 * num_chars).enumerate() {
+        if let Some(len) = if set.is_bracket(char) {
+            if let Some(bracket) = set.opening_bracket_to_closing(char) {
+                if index >= num_chars
                     && brackets
                         .first()
-                        .map(|ch| ch.index == index - val.len())
+                        .map(|ch| ch.index == index - num_chars)
                         .unwrap_or(false)
-                    || brackets.len() + 1 == val.len()
+                    || brackets.len() + 1 == num_chars
                 {
                     // Break loop because longest subsequence either already found or 0
                     break;
@@ -96,7 +158,7 @@ pub fn create_longest_substring(val: &str) -> Result<String, Error> {
                         brackets.truncate(0);
 
                         // If end of the string is reached, no need to go further
-                        if index >= val.len() {
+                        if index >= num_chars {
                             break;
                         }
 
@@ -113,8 +175,8 @@ pub fn create_longest_substring(val: &str) -> Result<String, Error> {
                 .or_else(|| Some(prev_valid_len + 1))
         } {
             if len > max_len {
-                if len >= val.len() {
-                    return Ok("Infinite".to_owned());
+                if len >= num_chars {
+                    return Span::Infinite;
                 }
                 max_len = len;
                 max_end = index + 1;
@@ -126,15 +188,17 @@ pub fn create_longest_substring(val: &str) -> Result<String, Error> {
         }
     }
 
-    Ok(if max_end > val.len() {
-        format!(
-            "{}{}",
-            &val[max_end - max_len..val.len()],
-            &val[0..max_end - val.len()]
-        )
+    if max_end > num_chars {
+        Span::WrapAround {
+            start: byte_offset(max_end - max_len),
+            end: byte_offset(max_end - num_chars),
+        }
     } else {
-        val[max_end - max_len..max_end].to_owned()
-    })
+        Span::Contiguous {
+            start: byte_offset(max_end - max_len),
+            end: byte_offset(max_end),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -143,80 +207,115 @@ mod tests {
 
     #[test]
     fn empty() {
-        assert_eq!(create_longest_substring("").unwrap(), "");
-        assert_eq!(create_longest_substring("(").unwrap(), "");
-        assert_eq!(create_longest_substring("(})").unwrap(), "");
-        assert_eq!(create_longest_substring("([)]]})").unwrap(), "");
-        assert_eq!(create_longest_substring("(((").unwrap(), "");
+        assert_eq!(create_longest_substring(""), "");
+        assert_eq!(create_longest_substring("("), "");
+        assert_eq!(create_longest_substring("(})"), "");
+        assert_eq!(create_longest_substring("([)]]})"), "");
+        assert_eq!(create_longest_substring("((("), "");
     }
 
     #[test]
     fn without_brackets() {
-        assert_eq!(create_longest_substring("abc").unwrap(), "Infinite");
-        assert_eq!(create_longest_substring("pasd").unwrap(), "Infinite");
-        assert_eq!(create_longest_substring("zxc").unwrap(), "Infinite");
+        assert_eq!(create_longest_substring("abc"), "Infinite");
+        assert_eq!(create_longest_substring("pasd"), "Infinite");
+        assert_eq!(create_longest_substring("zxc"), "Infinite");
     }
 
     #[test]
     fn with_equal_brackets() {
-        assert_eq!(create_longest_substring("(a(b)c)").unwrap(), "Infinite");
-        assert_eq!(create_longest_substring("{(a[b]c)}").unwrap(), "Infinite");
-        assert_eq!(create_longest_substring("a)b)(c(d").unwrap(), "Infinite");
-        assert_eq!(
-            create_longest_substring("[[g][f]]d))j}{k}{(l(").unwrap(),
-            "Infinite"
-        );
+        assert_eq!(create_longest_substring("(a(b)c)"), "Infinite");
+        assert_eq!(create_longest_substring("{(a[b]c)}"), "Infinite");
+        assert_eq!(create_longest_substring("a)b)(c(d"), "Infinite");
+        assert_eq!(create_longest_substring("[[g][f]]d))j}{k}{(l("), "Infinite");
         assert_eq!(
-            create_longest_substring(")p)}{q}i{((x[[]z[]y]o").unwrap(),
+            create_longest_substring(")p)}{q}i{((x[[]z[]y]o"),
             "Infinite"
         );
-        assert_eq!(create_longest_substring("q))]w[e((r").unwrap(), "Infinite");
+        assert_eq!(create_longest_substring("q))]w[e((r"), "Infinite");
     }
 
     #[test]
     fn finite() {
-        assert_eq!(create_longest_substring("))[((").unwrap(), "(())");
-        assert_eq!(create_longest_substring("])}([{}").unwrap(), "([{}])");
-        assert_eq!(create_longest_substring(")}([{}]").unwrap(), "([{}])");
+        assert_eq!(create_longest_substring("))[(("), "(())");
+        assert_eq!(create_longest_substring("])}([{}"), "([{}])");
+        assert_eq!(create_longest_substring(")}([{}]"), "([{}])");
+        assert_eq!(create_longest_substring("])}b(a[{efg}"), "b(a[{efg}])");
+        assert_eq!(create_longest_substring(")}(m[{o}]oops"), "(m[{o}]oops)");
+        assert_eq!(create_longest_substring("}}}a((("), "a");
+        assert_eq!(create_longest_substring("(a(b(d"), "a");
+        assert_eq!(create_longest_substring("(a(bc(d"), "bc");
+        assert_eq!(create_longest_substring("ab()(d"), "dab()");
+        assert_eq!(create_longest_substring("ab()]abc()(}}dr"), "drab()");
         assert_eq!(
-            create_longest_substring("])}b(a[{efg}").unwrap(),
-            "b(a[{efg}])"
-        );
-        assert_eq!(
-            create_longest_substring(")}(m[{o}]oops").unwrap(),
-            "(m[{o}]oops)"
-        );
-        assert_eq!(create_longest_substring("}}}a(((").unwrap(), "a");
-        assert_eq!(create_longest_substring("(a(b(d").unwrap(), "a");
-        assert_eq!(create_longest_substring("(a(bc(d").unwrap(), "bc");
-        assert_eq!(create_longest_substring("ab()(d").unwrap(), "dab()");
-        assert_eq!(
-            create_longest_substring("ab()]abc()(}}dr").unwrap(),
-            "drab()"
-        );
-        assert_eq!(
-            create_longest_substring("(aaaaaaabbbbbcccccc").unwrap(),
+            create_longest_substring("(aaaaaaabbbbbcccccc"),
             "aaaaaaabbbbbcccccc"
         );
         assert_eq!(
-            create_longest_substring(")aaaaaaabbbbbcccccc").unwrap(),
+            create_longest_substring(")aaaaaaabbbbbcccccc"),
             "aaaaaaabbbbbcccccc"
         );
     }
 
     #[test]
-    fn invalid() {
+    fn unicode() {
+        assert_eq!(create_longest_substring("(🖐){✊}"), "Infinite");
+        assert_eq!(create_longest_substring("🦅"), "Infinite");
+        assert_eq!(create_longest_substring("(🦅"), "🦅");
+        assert_eq!(create_longest_substring("🦅)(🐍"), "Infinite");
+    }
+
+    #[test]
+    fn custom_bracket_set() {
+        let angle_quotes = BracketSet::new([('<', '>'), ('«', '»')]);
         assert_eq!(
-            create_longest_substring("(🖐)){✊}").unwrap_err(),
-            Error::NonByteChar
+            create_longest_substring_with_set("y<a>x<b", &angle_quotes),
+            "by<a>x"
         );
         assert_eq!(
-            create_longest_substring("(🖐){✊}").unwrap_err(),
-            Error::NonByteChar
+            create_longest_substring_with_set("x>y<a«b»c", &angle_quotes),
+            "Infinite"
         );
         assert_eq!(
-            create_longest_substring("🦅").unwrap_err(),
-            Error::NonByteChar
+            create_longest_substring_with_set("<a>", &BracketSet::default()),
+            "Infinite"
         );
     }
+
+    #[test]
+    fn cow_borrows_contiguous_and_infinite() {
+        assert!(matches!(
+            create_longest_substring_cow("abc"),
+            Cow::Borrowed("Infinite")
+        ));
+        assert!(matches!(
+            create_longest_substring_cow("(aaaaaaabbbbbcccccc"),
+            Cow::Borrowed("aaaaaaabbbbbcccccc")
+        ));
+    }
+
+    #[test]
+    fn cow_owns_wrap_around() {
+        assert!(matches!(
+            create_longest_substring_cow("ab()(d"),
+            Cow::Owned(ref s) if s == "dab()"
+        ));
+    }
+
+    #[test]
+    fn cow_matches_owned() {
+        for val in [
+            "",
+            "(",
+            "abc",
+            "))[((",
+            "ab()(d",
+            "])}b(a[{efg}",
+            "(aaaaaaabbbbbcccccc",
+        ] {
+            assert_eq!(
+                create_longest_substring_cow(val),
+                create_longest_substring(val)
+            );
+        }
+    }
 }
@@ -0,0 +1,202 @@
+use crate::BracketSet;
+
+///
+/// Yields every locally-maximal valid-bracket substring of `val` (matching against the
+/// default [`BracketSet`]), together with its starting byte offset, in order of
+/// appearance.
+///
+pub fn maximal_valid_substrings(val: &str) -> impl Iterator<Item = (usize, &str)> {
+    maximal_valid_substrings_with_set(val, &BracketSet::default())
+}
+
+///
+/// Yields every locally-maximal valid-bracket substring of `val` (matching against `set`),
+/// together with its starting byte offset, in order of appearance.
+///
+/// Reuses the stack/`prev_valid_len` machinery that [`find_longest_span`](crate) uses to
+/// track the single longest run: at every position it computes the length of the valid
+/// substring ending there exactly as that scan does, merging adjacent valid segments the
+/// same way. A parallel stack of "run so far" snapshots, pushed and popped alongside the
+/// bracket stack, lets a run that's shadowed by a still-open bracket be recovered later if
+/// that bracket closes, or flushed as its own maximal substring if it never does (or if an
+/// unmatched closer forces a reset). Each completed run is recorded once it is no longer
+/// reachable, instead of keeping only the single longest one.
+///
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn maximal_valid_substrings_with_set<'a>(
+    val: &'a str,
+    set: &BracketSet,
+) -> impl Iterator<Item = (usize, &'a str)> {
+    #[derive(Debug, Copy, Clone)]
+    struct CharPos {
+        val: char,
+        index: usize,
+    }
+
+    let char_byte_offsets: Vec<usize> = val
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(val.len()))
+        .collect();
+    let byte_offset = move |char_index: usize| char_byte_offsets[char_index];
+
+    let mut brackets = Vec::<CharPos>::new();
+
+    // Length of valid sequential substring predecessor
+    let mut prev_valid_len = 0;
+
+    let mut spans = Vec::<(usize, usize)>::new();
+    // The run (char indices) being extended at the current nesting depth.
+    let mut current_run: Option<(usize, usize)> = None;
+    // Snapshot of `current_run` taken every time a bracket is pushed, so it can be
+    // recovered if that bracket's subtree later merges back into it, or flushed on its
+    // own otherwise. Parallels `brackets` one-to-one.
+    let mut run_stack = Vec::<Option<(usize, usize)>>::new();
+
+    for (index, char) in val.chars().enumerate() {
+        if let Some(len) = if set.is_bracket(char) {
+            if let Some(bracket) = set.opening_bracket_to_closing(char) {
+                brackets.push(CharPos {
+                    val: bracket,
+                    index,
+                });
+                run_stack.push(current_run.take());
+
+                None
+            } else {
+                match brackets.pop().and_then(|last| {
+                    if last.val == char {
+                        brackets
+                            .last()
+                            // Need to also capture characters between previous and last
+                            .map(|prev| index - prev.index)
+                            .or_else(|| Some(1 + index - last.index + prev_valid_len))
+                    } else {
+                        None
+                    }
+                }) {
+                    // Reset brackets and prev_valid_len because current sequence is
+                    // invalid: nothing shadowed by the brackets just discarded can ever be
+                    // reached again, so flush it all as its own maximal runs.
+                    None => {
+                        spans.extend(run_stack.drain(..).flatten());
+                        spans.extend(current_run.take());
+
+                        prev_valid_len = 0;
+                        brackets.truncate(0);
+
+                        None
+                    }
+                    v => {
+                        // A matched close always yields a run that's a superset of
+                        // whatever was shadowed at this depth, so that snapshot can be
+                        // dropped.
+                        run_stack.pop();
+                        v
+                    }
+                }
+            }
+        } else {
+            brackets
+                .last()
+                // Calculate distance between current character and last bracket in brackets
+                .map(|prev| index - prev.index)
+                .or_else(|| Some(prev_valid_len + 1))
+        } {
+            current_run = Some((index + 1 - len, index + 1));
+
+            if brackets.is_empty() {
+                prev_valid_len = len;
+            }
+        }
+    }
+
+    spans.extend(run_stack.into_iter().flatten());
+    spans.extend(current_run);
+
+    spans.into_iter().map(move |(start, end)| {
+        (
+            byte_offset(start),
+            &val[byte_offset(start)..byte_offset(end)],
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_brackets_has_one_run() {
+        assert_eq!(
+            maximal_valid_substrings("abc").collect::<Vec<_>>(),
+            vec![(0, "abc")]
+        );
+    }
+
+    #[test]
+    fn empty_string_has_no_runs() {
+        assert_eq!(maximal_valid_substrings("").collect::<Vec<_>>(), vec![]);
+    }
+
+    #[test]
+    fn finds_disjoint_runs() {
+        assert_eq!(
+            maximal_valid_substrings("a(b)c]d(e)f").collect::<Vec<_>>(),
+            vec![(0, "a(b)c"), (6, "d(e)f")]
+        );
+    }
+
+    #[test]
+    fn merges_adjacent_valid_segments() {
+        assert_eq!(
+            maximal_valid_substrings("()()").collect::<Vec<_>>(),
+            vec![(0, "()()")]
+        );
+        assert_eq!(
+            maximal_valid_substrings("))[((").collect::<Vec<_>>(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn merges_across_nested_nonempty_stack() {
+        // "c(b)d" only reconnects with the leading "a" once the outer '(' closes and the
+        // bracket stack fully empties again.
+        assert_eq!(
+            maximal_valid_substrings("a(c(b)d)e").collect::<Vec<_>>(),
+            vec![(0, "a(c(b)d)e")]
+        );
+    }
+
+    #[test]
+    fn flushes_run_shadowed_by_an_unmatched_opening_bracket() {
+        // The leftover unmatched '(' at index 1 never closes, so "x" can never reconnect
+        // with "(b)y" and both are flushed as separate maximal runs.
+        assert_eq!(
+            maximal_valid_substrings("x((b)y").collect::<Vec<_>>(),
+            vec![(0, "x"), (2, "(b)y")]
+        );
+    }
+
+    #[test]
+    fn flushes_shadowed_runs_on_reset() {
+        // ']' can't close the '(' opened at index 1, forcing a reset that strands both the
+        // leading "a" and the nested "b" as their own maximal runs.
+        assert_eq!(
+            maximal_valid_substrings("a(b]c").collect::<Vec<_>>(),
+            vec![(0, "a"), (2, "b"), (4, "c")]
+        );
+    }
+
+    #[test]
+    fn respects_custom_bracket_set() {
+        let angle_quotes = BracketSet::new([('<', '>')]);
+        assert_eq!(
+            maximal_valid_substrings_with_set("x<a>y>z<b", &angle_quotes).collect::<Vec<_>>(),
+            vec![(0, "x<a>y"), (6, "z"), (8, "b")]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::BracketSet;
+
+///
+/// Computes the fewest single-character bracket insertions needed to turn `val` into a
+/// fully balanced sequence, matching brackets against `set`. Unlike
+/// [`create_longest_substring`](crate::create_longest_substring), this looks at the whole
+/// (non-cyclic) input rather than the longest already-valid substring.
+///
+/// Scans left to right keeping a stack of pending openers: a closer matching the top of
+/// the stack pops it, while an unmatchable closer means a synthetic opener was needed
+/// (`inserted` is incremented and the stack is left untouched). Whatever remains on the
+/// stack at the end each needs a synthetic closer.
+///
+/// Non-bracket characters are ignored.
+///
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn min_repair_insertions(val: &str, set: &BracketSet) -> usize {
+    let mut stack = Vec::<char>::new();
+    let mut inserted = 0;
+
+    for char in val.chars() {
+        if let Some(closing) = set.opening_bracket_to_closing(char) {
+            stack.push(closing);
+        } else if set.is_closing(char) {
+            if stack.last() == Some(&char) {
+                stack.pop();
+            } else {
+                inserted += 1;
+            }
+        }
+    }
+
+    inserted + stack.len()
+}
+
+///
+/// Repairs `val` into a fully balanced bracket sequence by inserting the minimal number of
+/// synthetic brackets, matching brackets against `set`. See [`min_repair_insertions`] for
+/// the count-only variant and the algorithm it implements.
+///
+/// Time complexity: O(n)
+/// Space complexity: O(n)
+///
+pub fn repair(val: &str, set: &BracketSet) -> String {
+    let mut stack = Vec::<char>::new();
+    let mut result = String::with_capacity(val.len());
+
+    for char in val.chars() {
+        if let Some(closing) = set.opening_bracket_to_closing(char) {
+            stack.push(closing);
+            result.push(char);
+        } else if set.is_closing(char) {
+            if stack.last() == Some(&char) {
+                stack.pop();
+            } else if let Some(opening) = set.closing_bracket_to_opening(char) {
+                // Unmatchable closer: a synthetic opener was needed right before it.
+                result.push(opening);
+            }
+            result.push(char);
+        } else {
+            result.push(char);
+        }
+    }
+
+    // Every opener left on the stack still needs its synthetic closer, innermost first.
+    while let Some(closing) = stack.pop() {
+        result.push(closing);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_non_bracket_only() {
+        let set = BracketSet::default();
+        assert_eq!(min_repair_insertions("", &set), 0);
+        assert_eq!(min_repair_insertions("abc", &set), 0);
+        assert_eq!(repair("", &set), "");
+        assert_eq!(repair("abc", &set), "abc");
+    }
+
+    #[test]
+    fn already_balanced() {
+        let set = BracketSet::default();
+        assert_eq!(min_repair_insertions("(a[b]c)", &set), 0);
+        assert_eq!(repair("(a[b]c)", &set), "(a[b]c)");
+    }
+
+    #[test]
+    fn unmatched_closers_and_openers() {
+        let set = BracketSet::default();
+        assert_eq!(min_repair_insertions("(]", &set), 2);
+        assert_eq!(repair("(]", &set), "([])");
+        assert_eq!(min_repair_insertions("((", &set), 2);
+        assert_eq!(repair("((", &set), "(())");
+        assert_eq!(min_repair_insertions("))", &set), 2);
+        assert_eq!(repair("))", &set), "()()");
+    }
+
+    #[test]
+    fn mixed() {
+        let set = BracketSet::default();
+        assert_eq!(min_repair_insertions("(a(b)c", &set), 1);
+        assert_eq!(repair("(a(b)c", &set), "(a(b)c)");
+        assert_eq!(min_repair_insertions("}}}a(((", &set), 6);
+        assert_eq!(repair("}}}a(((", &set), "{}{}{}a((()))");
+    }
+}